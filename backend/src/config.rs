@@ -0,0 +1,166 @@
+//! Layered application configuration.
+//!
+//! Replaces the scattered `std::env::var` lookups that used to be sprinkled
+//! through `main` with a single typed [`Config`], loaded in three layers
+//! (lowest to highest precedence): built-in defaults, an optional
+//! `config.toml`, then environment variables. Loading happens once at
+//! startup and fails fast with a descriptive error rather than surfacing a
+//! bad value later as a bind panic.
+
+use figment::{
+    providers::{Env, Format, Serialized, Toml},
+    Figment,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub database_url: String,
+    pub server_host: String,
+    pub server_port: u16,
+    #[serde(deserialize_with = "deserialize_csv_list")]
+    pub cors_allowed_origins: Vec<String>,
+    #[serde(deserialize_with = "deserialize_csv_list")]
+    pub cors_allowed_headers: Vec<String>,
+    pub request_timeout_secs: u64,
+    pub max_body_bytes: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            database_url: "sqlite:stellar_insights.db".to_string(),
+            server_host: "127.0.0.1".to_string(),
+            server_port: 8080,
+            cors_allowed_origins: Vec::new(),
+            cors_allowed_headers: Vec::new(),
+            request_timeout_secs: 30,
+            max_body_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl Config {
+    /// Loads defaults, then `config.toml` (if present), then environment
+    /// variables, and validates the result. Env var names match the field
+    /// names this config replaces (`DATABASE_URL`, `SERVER_PORT`, ...), so
+    /// existing deployments don't need to change anything.
+    pub fn load() -> anyhow::Result<Self> {
+        let config: Config = Figment::from(Serialized::defaults(Config::default()))
+            .merge(Toml::file("config.toml"))
+            .merge(Env::raw())
+            .extract()?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.server_port == 0 {
+            anyhow::bail!("server_port must be a non-zero u16");
+        }
+        if self.server_host.trim().is_empty() {
+            anyhow::bail!("server_host must not be empty");
+        }
+        if self.request_timeout_secs == 0 {
+            anyhow::bail!("request_timeout_secs must be non-zero, or every request times out instantly");
+        }
+        if self.max_body_bytes == 0 {
+            anyhow::bail!("max_body_bytes must be non-zero, or every request body is rejected");
+        }
+        Ok(())
+    }
+
+    pub fn server_addr(&self) -> String {
+        format!("{}:{}", self.server_host, self.server_port)
+    }
+}
+
+/// Accepts both a comma-separated string (as read from the environment)
+/// and a native TOML array (as written in `config.toml`). Used for both
+/// `cors_allowed_origins` and `cors_allowed_headers`.
+fn deserialize_csv_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum CsvList {
+        Csv(String),
+        List(Vec<String>),
+    }
+
+    Ok(match CsvList::deserialize(deserializer)? {
+        CsvList::Csv(s) => s
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect(),
+        CsvList::List(list) => list,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct CsvWrapper {
+        #[serde(deserialize_with = "deserialize_csv_list")]
+        values: Vec<String>,
+    }
+
+    #[test]
+    fn csv_list_splits_and_trims() {
+        let parsed: CsvWrapper = serde_json::from_str(r#"{"values": " a, b ,, c "}"#).unwrap();
+        assert_eq!(parsed.values, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn csv_list_accepts_native_list() {
+        let parsed: CsvWrapper = serde_json::from_str(r#"{"values": ["a", "b"]}"#).unwrap();
+        assert_eq!(parsed.values, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn validate_accepts_defaults() {
+        assert!(Config::default().validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_zero_port() {
+        let config = Config {
+            server_port: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_empty_host() {
+        let config = Config {
+            server_host: "  ".to_string(),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_zero_request_timeout() {
+        let config = Config {
+            request_timeout_secs: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_zero_max_body_bytes() {
+        let config = Config {
+            max_body_bytes: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
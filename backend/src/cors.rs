@@ -0,0 +1,204 @@
+//! Runtime-mutable CORS origin allowlist.
+//!
+//! Unlike the static `CORS_ALLOWED_ORIGINS` list read once at startup, this
+//! registry can be grown (or reset) while the server is running via the
+//! `/api/cors/origins` management routes, and is persisted through
+//! [`Database`] so the set survives restarts.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use subtle::ConstantTimeEq;
+use tokio::sync::RwLock;
+use tower_http::cors::AllowOrigin;
+
+use crate::database::Database;
+
+/// Shared, lock-guarded set of origins allowed to make cross-origin requests.
+#[derive(Clone)]
+pub struct OriginRegistry {
+    origins: Arc<RwLock<HashSet<HeaderValue>>>,
+}
+
+impl OriginRegistry {
+    pub fn new(initial: impl IntoIterator<Item = HeaderValue>) -> Self {
+        Self {
+            origins: Arc::new(RwLock::new(initial.into_iter().collect())),
+        }
+    }
+
+    /// Loads the persisted origin set from the database, if any.
+    pub async fn load(db: &Database) -> anyhow::Result<Self> {
+        let origins = db
+            .load_cors_origins()
+            .await?
+            .into_iter()
+            .filter_map(|origin| HeaderValue::from_str(&origin).ok())
+            .collect::<HashSet<_>>();
+        Ok(Self {
+            origins: Arc::new(RwLock::new(origins)),
+        })
+    }
+
+    /// Builds the `tower-http` predicate that checks an incoming `Origin`
+    /// header against the live set. An empty set is permissive (matches the
+    /// original behavior when no origin has been configured yet) rather
+    /// than denying everything; once at least one origin is added — at
+    /// startup or via `POST /api/cors/origins` — only that set is allowed,
+    /// live, with no restart required. Reads the lock once per request and
+    /// never panics on a malformed header.
+    pub fn allow_origin(&self) -> AllowOrigin {
+        let origins = self.origins.clone();
+        AllowOrigin::predicate(move |origin: &HeaderValue, _request_parts| {
+            origins
+                .try_read()
+                .map(|set| set.is_empty() || set.contains(origin))
+                .unwrap_or(false)
+        })
+    }
+
+    pub(crate) async fn insert(&self, origin: HeaderValue) {
+        self.origins.write().await.insert(origin);
+    }
+
+    async fn clear(&self) {
+        self.origins.write().await.clear();
+    }
+
+    pub(crate) async fn is_empty(&self) -> bool {
+        self.origins.read().await.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn new_registry_starts_empty() {
+        let registry = OriginRegistry::new(std::iter::empty());
+        assert!(registry.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn insert_is_no_longer_empty() {
+        let registry = OriginRegistry::new(std::iter::empty());
+        registry
+            .insert(HeaderValue::from_static("https://example.com"))
+            .await;
+        assert!(!registry.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn clear_empties_a_seeded_registry() {
+        let registry =
+            OriginRegistry::new([HeaderValue::from_static("https://example.com")]);
+        assert!(!registry.is_empty().await);
+        registry.clear().await;
+        assert!(registry.is_empty().await);
+    }
+
+    // `ADMIN_API_TOKEN` is process-global, so these cases live in one test
+    // instead of several that could race on it under parallel test runs.
+    #[test]
+    fn is_authorized_cases() {
+        std::env::set_var("ADMIN_API_TOKEN", "s3cret-token");
+
+        let headers = HeaderMap::new();
+        assert!(!is_authorized(&headers), "missing header is unauthorized");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            HeaderValue::from_static("s3cret-token"),
+        );
+        assert!(
+            !is_authorized(&headers),
+            "value without a Bearer prefix is unauthorized"
+        );
+
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer wrong-token"),
+        );
+        assert!(!is_authorized(&headers), "wrong token is unauthorized");
+
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer s3cret-token"),
+        );
+        assert!(is_authorized(&headers), "correct bearer token is authorized");
+
+        std::env::remove_var("ADMIN_API_TOKEN");
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AddOriginRequest {
+    origin: String,
+}
+
+/// `POST /api/cors/origins` — add an origin to the live allowlist.
+pub async fn add_origin(
+    State((registry, db)): State<(OriginRegistry, Arc<Database>)>,
+    headers: HeaderMap,
+    Json(payload): Json<AddOriginRequest>,
+) -> impl IntoResponse {
+    if !is_authorized(&headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let Ok(value) = HeaderValue::from_str(&payload.origin) else {
+        return (StatusCode::BAD_REQUEST, "invalid origin header value").into_response();
+    };
+
+    if let Err(err) = db.save_cors_origin(&payload.origin).await {
+        tracing::error!("failed to persist CORS origin: {err}");
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    registry.insert(value).await;
+
+    StatusCode::NO_CONTENT.into_response()
+}
+
+/// `POST /api/cors/origins/clear` — reset the live allowlist.
+pub async fn clear_origins(
+    State((registry, db)): State<(OriginRegistry, Arc<Database>)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_authorized(&headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    if let Err(err) = db.clear_cors_origins().await {
+        tracing::error!("failed to clear persisted CORS origins: {err}");
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    registry.clear().await;
+
+    StatusCode::NO_CONTENT.into_response()
+}
+
+/// Minimal bearer-token check against `ADMIN_API_TOKEN`. Management routes
+/// are the only authenticated ones in this service, so a single shared
+/// secret is enough for now. Compared in constant time since this is the
+/// one endpoint in the series meant to harden the service.
+fn is_authorized(headers: &HeaderMap) -> bool {
+    let Ok(expected) = std::env::var("ADMIN_API_TOKEN") else {
+        return false;
+    };
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| {
+            token.len() == expected.len() && token.as_bytes().ct_eq(expected.as_bytes()).into()
+        })
+}
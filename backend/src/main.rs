@@ -1,18 +1,114 @@
 use anyhow::Result;
 use axum::{
-    routing::{get, put},
-    Router,
+    error_handling::HandleErrorLayer,
+    extract::DefaultBodyLimit,
+    http::{HeaderName, HeaderValue, Method, StatusCode},
+    routing::{get, post, put},
+    BoxError, Router,
 };
 use dotenv::dotenv;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tower::ServiceBuilder;
 use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+use backend::config::Config;
+use backend::cors::OriginRegistry;
 use backend::database::Database;
 use backend::handlers::*;
 
+/// Parses `config.cors_allowed_headers` into `HeaderName`s, skipping and
+/// warning on anything that doesn't parse. Empty input means "don't
+/// restrict headers" (`Any`), same as the origin allowlist's empty case.
+fn parsed_allowed_headers(config: &Config) -> Vec<HeaderName> {
+    config
+        .cors_allowed_headers
+        .iter()
+        .filter_map(|header| match HeaderName::from_str(header) {
+            Ok(name) => Some(name),
+            Err(err) => {
+                tracing::warn!("ignoring invalid CORS header {header:?}: {err}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Builds the CORS layer and its backing [`OriginRegistry`].
+///
+/// The registry is seeded from the persisted set plus `config.cors_allowed_origins`,
+/// and the layer always checks origins against that *live* registry —
+/// never a one-time snapshot — so `POST /api/cors/origins` takes effect
+/// immediately even when the server started with an empty allowlist. An
+/// empty registry is permissive (any origin is allowed), matching this
+/// service's original behavior before the allowlist existed; adding the
+/// first origin, via config or the management endpoint, switches it over
+/// to restricting cross-origin requests to that set. Allowed headers are
+/// restricted to `config.cors_allowed_headers` when set, falling back to
+/// `Any` when the operator hasn't configured one.
+async fn build_cors_layer(db: &Database, config: &Config) -> Result<(CorsLayer, OriginRegistry)> {
+    let registry = OriginRegistry::load(db).await?;
+    for origin in &config.cors_allowed_origins {
+        match HeaderValue::from_str(origin) {
+            Ok(value) => registry.insert(value).await,
+            Err(err) => tracing::warn!("ignoring invalid CORS origin {origin:?}: {err}"),
+        }
+    }
+
+    if registry.is_empty().await {
+        tracing::warn!(
+            "no CORS origins configured at startup; allowing all cross-origin requests until one is added via config or POST /api/cors/origins"
+        );
+    }
+
+    let allowed_headers = parsed_allowed_headers(config);
+    let cors = CorsLayer::new()
+        .allow_origin(registry.allow_origin())
+        .allow_methods([Method::GET, Method::POST, Method::PUT]);
+    let cors = if allowed_headers.is_empty() {
+        cors.allow_headers(Any)
+    } else {
+        cors.allow_headers(allowed_headers)
+    };
+    Ok((cors, registry))
+}
+
+/// Resolves once either Ctrl+C or (on Unix) SIGTERM is received, so
+/// `axum::serve`'s graceful shutdown can let in-flight SQLite writes finish
+/// before the process exits. Also cancels `shutdown`, which the metrics
+/// worker polls so both it and the HTTP server drain together instead of
+/// the worker being aborted outright when `main` returns.
+async fn shutdown_signal(shutdown: CancellationToken) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("shutdown signal received, draining in-flight requests");
+    shutdown.cancel();
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Load environment variables
@@ -27,12 +123,14 @@ async fn main() -> Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    // Database connection
-    let database_url =
-        std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite:stellar_insights.db".to_string());
+    // Layered config: defaults -> config.toml -> environment. Fails fast on
+    // a bad value (e.g. an unparseable port) instead of surfacing later as
+    // a bind panic.
+    let config = Config::load()?;
 
+    // Database connection
     tracing::info!("Connecting to database...");
-    let options = SqliteConnectOptions::from_str(&database_url)?.create_if_missing(true);
+    let options = SqliteConnectOptions::from_str(&config.database_url)?.create_if_missing(true);
     let pool = SqlitePool::connect_with(options).await?;
 
     tracing::info!("Running database migrations...");
@@ -40,13 +138,29 @@ async fn main() -> Result<()> {
 
     let db = Arc::new(Database::new(pool));
 
+    // Background worker: refreshes anchor metrics on a cron schedule,
+    // sharing the same `Database` the HTTP app reads and writes through.
+    // `shutdown_token` is shared with the HTTP server's shutdown signal so
+    // the worker drains alongside it instead of being aborted when `main`
+    // returns.
+    let shutdown_token = CancellationToken::new();
+    let worker_db = db.clone();
+    let worker_shutdown = shutdown_token.clone();
+    let worker_handle = tokio::spawn(async move {
+        if let Err(err) = backend::worker::spawn(worker_db, worker_shutdown).await {
+            tracing::error!("metrics refresh worker exited: {err}");
+        }
+    });
+
     // CORS configuration
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    let (cors, origin_registry) = build_cors_layer(&db, &config).await?;
 
     // Build router
+    let cors_management = Router::new()
+        .route("/api/cors/origins", post(backend::cors::add_origin))
+        .route("/api/cors/origins/clear", post(backend::cors::clear_origins))
+        .with_state((origin_registry, db.clone()));
+
     let app = Router::new()
         .route("/health", get(health_check))
         .route("/api/anchors", get(list_anchors).post(create_anchor))
@@ -60,17 +174,61 @@ async fn main() -> Result<()> {
             "/api/anchors/:id/assets",
             get(get_anchor_assets).post(create_anchor_asset),
         )
-        .layer(cors)
-        .with_state(db);
+        .with_state(db)
+        .merge(cors_management)
+        .layer(cors);
+
+    // Request guards: reject slow or oversized requests instead of tying up
+    // connections indefinitely. `TimeoutLayer`'s `Elapsed` error can't fold
+    // into the router's `Infallible`, so `HandleErrorLayer` turns it into a
+    // response before `Router::layer` ever sees it.
+    let app = app
+        .layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(|_: BoxError| async {
+                    StatusCode::REQUEST_TIMEOUT
+                }))
+                .timeout(Duration::from_secs(config.request_timeout_secs)),
+        )
+        .layer(DefaultBodyLimit::max(config.max_body_bytes));
 
     // Start server
-    let host = std::env::var("SERVER_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
-    let port = std::env::var("SERVER_PORT").unwrap_or_else(|_| "8080".to_string());
-    let addr = format!("{}:{}", host, port);
+    let addr = config.server_addr();
 
     tracing::info!("Server starting on {}", addr);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal(shutdown_token))
+        .await?;
+
+    if let Err(err) = worker_handle.await {
+        tracing::error!("metrics refresh worker task panicked: {err}");
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsed_allowed_headers_is_empty_when_unset() {
+        let config = Config::default();
+        assert!(parsed_allowed_headers(&config).is_empty());
+    }
+
+    #[test]
+    fn parsed_allowed_headers_skips_invalid_entries() {
+        let config = Config {
+            cors_allowed_headers: vec![
+                "content-type".to_string(),
+                "not a valid header".to_string(),
+                "authorization".to_string(),
+            ],
+            ..Config::default()
+        };
+        let headers = parsed_allowed_headers(&config);
+        assert_eq!(headers, vec![HeaderName::from_static("content-type"), HeaderName::from_static("authorization")]);
+    }
+}
@@ -0,0 +1,100 @@
+//! Background job that periodically recomputes every anchor's metrics.
+//!
+//! Runs as its own `apalis` worker alongside the HTTP server so the
+//! dashboard stays fresh without an operator hitting
+//! `PUT /api/anchors/:id/metrics` by hand. Ticks come straight from
+//! `apalis-cron`'s in-process `CronStream` rather than an `apalis-sql`
+//! storage backend: a missed refresh cycle just leaves anchors stale until
+//! the next tick, not lost job state that needs to survive a restart, so
+//! there's nothing here worth paying a SQLite table and migration for.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use apalis::prelude::*;
+use apalis_cron::{CronStream, Schedule};
+use chrono::{DateTime, Utc};
+use tokio_util::sync::CancellationToken;
+
+use crate::database::Database;
+use crate::metrics;
+
+const DEFAULT_SCHEDULE: &str = "0 */15 * * * *";
+
+#[derive(Clone)]
+struct RefreshMetricsTick(DateTime<Utc>);
+
+impl From<DateTime<Utc>> for RefreshMetricsTick {
+    fn from(tick: DateTime<Utc>) -> Self {
+        RefreshMetricsTick(tick)
+    }
+}
+
+/// Walks every anchor and recomputes its metrics, writing each one back
+/// through the same [`Database::update_anchor_metrics`] call the manual
+/// HTTP handler uses.
+async fn refresh_all_anchor_metrics(_tick: RefreshMetricsTick, db: Data<Arc<Database>>) {
+    let anchors = match db.list_anchors().await {
+        Ok(anchors) => anchors,
+        Err(err) => {
+            tracing::error!("metrics refresh: failed to list anchors: {err}");
+            return;
+        }
+    };
+
+    for anchor in anchors {
+        let updated = match metrics::compute_anchor_metrics(&anchor.stellar_account).await {
+            Ok(updated) => updated,
+            Err(err) => {
+                tracing::warn!(
+                    "metrics refresh: failed to recompute metrics for {}: {err}",
+                    anchor.stellar_account
+                );
+                continue;
+            }
+        };
+
+        if let Err(err) = db.update_anchor_metrics(anchor.id, updated).await {
+            tracing::error!(
+                "metrics refresh: failed to persist metrics for {}: {err}",
+                anchor.stellar_account
+            );
+        }
+    }
+
+    tracing::info!("metrics refresh: cycle complete");
+}
+
+/// Spawns the cron worker. Schedule is read from `METRICS_REFRESH_CRON`
+/// (six-field cron, seconds first), defaulting to every 15 minutes. `db` is
+/// the same `Arc<Database>` the HTTP app reads and writes through, so both
+/// stay consistent.
+///
+/// `shutdown` is shared with `main`'s signal handler: once it's cancelled,
+/// the monitor stops pulling new ticks and this future resolves after the
+/// in-flight refresh (if any) finishes writing, so the caller can safely
+/// await the `JoinHandle` this runs under before the process exits.
+pub async fn spawn(db: Arc<Database>, shutdown: CancellationToken) -> anyhow::Result<()> {
+    let cron_expr =
+        std::env::var("METRICS_REFRESH_CRON").unwrap_or_else(|_| DEFAULT_SCHEDULE.to_string());
+    let schedule = Schedule::from_str(&cron_expr)
+        .map_err(|err| anyhow::anyhow!("invalid METRICS_REFRESH_CRON {cron_expr:?}: {err}"))?;
+
+    tracing::info!("metrics refresh worker scheduled: {cron_expr}");
+
+    Monitor::<TokioExecutor>::new()
+        .register(
+            WorkerBuilder::new("metrics-refresh")
+                .data(db)
+                .stream(CronStream::new(schedule).into_stream())
+                .build_fn(refresh_all_anchor_metrics),
+        )
+        .run_with_signal(async move {
+            shutdown.cancelled().await;
+            tracing::info!("metrics refresh worker: shutdown signal received, draining");
+            Ok(())
+        })
+        .await?;
+
+    Ok(())
+}
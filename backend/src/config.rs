@@ -0,0 +1,436 @@
+//! Layered application configuration.
+//!
+//! Replaces the scattered `std::env::var` lookups that used to be sprinkled
+//! through `main` with a single typed [`Config`], loaded in three layers
+//! (lowest to highest precedence): built-in defaults, an optional
+//! `config.toml`, then environment variables. Loading happens once at
+//! startup and fails fast with a descriptive error rather than surfacing a
+//! bad value later as a bind panic.
+
+use figment::{
+    providers::{Env, Format, Serialized, Toml},
+    Figment,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub database_url: String,
+    pub server_host: String,
+    pub server_port: u16,
+    #[serde(deserialize_with = "deserialize_csv_list")]
+    pub cors_allowed_origins: Vec<String>,
+    #[serde(deserialize_with = "deserialize_csv_list")]
+    pub cors_allowed_headers: Vec<String>,
+    pub request_timeout_secs: u64,
+    pub max_body_bytes: usize,
+    /// Higher body limit applied only to `POST /api/anchors/bulk`, which
+    /// legitimately needs to accept more than a single anchor's worth of
+    /// JSON. See `main`'s router construction for how this overrides
+    /// `max_body_bytes` on that one route.
+    pub max_bulk_body_bytes: usize,
+    pub rate_limit_per_minute: u32,
+    /// IPs or CIDR blocks (e.g. `"10.0.0.0/8"`, comma-separated) allowed to
+    /// set `X-Forwarded-For` for `backend::ratelimit::RateLimiter`. Empty by
+    /// default, meaning no peer is trusted and the limiter always keys on
+    /// the socket's peer address — a deployment behind a reverse proxy (or
+    /// serving over the Unix socket `main::serve_unix` sets up, whose peer
+    /// address is always the `127.0.0.1` placeholder) must opt in
+    /// explicitly, or a client can spoof a fresh IP on every request to
+    /// dodge the limiter.
+    #[serde(deserialize_with = "deserialize_csv_list")]
+    pub trusted_proxies: Vec<String>,
+    pub horizon_url: String,
+    /// Horizon instance queried for anchors on the `testnet` network. See
+    /// `horizon_url` for the `public` (mainnet) counterpart.
+    pub horizon_testnet_url: String,
+    /// Timeout applied to every outbound Horizon request by
+    /// `backend::horizon::HorizonClient`.
+    pub horizon_request_timeout_secs: u64,
+    /// Combined rate cap, across every Horizon-backed feature, applied by
+    /// `backend::horizon::HorizonClient`'s internal token bucket. Horizon's
+    /// own public rate limit is generous but not unlimited, and this
+    /// service now has several independent callers (anchor creation,
+    /// trustline verification, payment ingestion) that could otherwise
+    /// burst past it in combination even though none of them would alone.
+    pub horizon_rate_limit_per_second: u32,
+    /// Passed to `AnyPoolOptions::max_connections` by `main::configure_pool`.
+    pub db_max_connections: u32,
+    /// Passed to `AnyPoolOptions::acquire_timeout` by `main::configure_pool`.
+    pub db_acquire_timeout_secs: u64,
+    /// TTL, in seconds, for `Database`'s in-memory `get_anchor` cache. `0`
+    /// disables the cache entirely, which is also the default: caching is
+    /// an opt-in optimization for a deployment with hot anchor reads, not
+    /// something every install should pay the memory cost of.
+    pub cache_ttl_secs: u64,
+    /// Page size applied to a paginated list endpoint (`GET /api/anchors`
+    /// and friends) when the caller omits `?limit=`. See
+    /// [`Config::max_page_size`] for the upper bound on an explicit one.
+    pub default_page_size: i64,
+    /// Largest `?limit=` a paginated list endpoint will honor; anything
+    /// above it is silently clamped down, with the applied value reported
+    /// back via the `X-Page-Limit` response header rather than left for
+    /// the caller to guess. `?limit=0` or negative is rejected outright
+    /// instead, since that's a caller mistake rather than a request for a
+    /// smaller page.
+    pub max_page_size: i64,
+    /// Starting state of `backend::maintenance::MaintenanceMode`. Read once
+    /// at startup like every other `Config` field; toggling it afterwards
+    /// without a restart is what the `/api/admin/maintenance` routes are
+    /// for.
+    pub maintenance_mode: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            database_url: "sqlite:stellar_insights.db".to_string(),
+            server_host: "127.0.0.1".to_string(),
+            server_port: 8080,
+            cors_allowed_origins: Vec::new(),
+            cors_allowed_headers: Vec::new(),
+            request_timeout_secs: 30,
+            max_body_bytes: 256 * 1024,
+            max_bulk_body_bytes: 4 * 1024 * 1024,
+            rate_limit_per_minute: 120,
+            trusted_proxies: Vec::new(),
+            horizon_url: "https://horizon.stellar.org".to_string(),
+            horizon_testnet_url: "https://horizon-testnet.stellar.org".to_string(),
+            horizon_request_timeout_secs: 10,
+            horizon_rate_limit_per_second: 10,
+            db_max_connections: 10,
+            db_acquire_timeout_secs: 30,
+            cache_ttl_secs: 0,
+            default_page_size: 20,
+            max_page_size: 100,
+            maintenance_mode: false,
+        }
+    }
+}
+
+impl Config {
+    /// Loads defaults, then `config.toml` (if present), then environment
+    /// variables, and validates the result. Env var names match the field
+    /// names this config replaces (`DATABASE_URL`, `SERVER_PORT`, ...), so
+    /// existing deployments don't need to change anything.
+    pub fn load() -> anyhow::Result<Self> {
+        let config: Config = Figment::from(Serialized::defaults(Config::default()))
+            .merge(Toml::file("config.toml"))
+            .merge(Env::raw())
+            .extract()?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Collects every problem with the loaded config rather than stopping
+    /// at the first one, so a misconfigured deployment finds out about all
+    /// of its bad values in one failed startup instead of fixing them one
+    /// `cargo run` at a time.
+    fn validate(&self) -> anyhow::Result<()> {
+        let mut problems = Vec::new();
+
+        if self.server_port == 0 {
+            problems.push("server_port must be a non-zero u16");
+        }
+        if self.server_host.trim().is_empty() {
+            problems.push("server_host must not be empty");
+        }
+        if self.request_timeout_secs == 0 {
+            problems
+                .push("request_timeout_secs must be non-zero, or every request times out instantly");
+        }
+        if self.max_body_bytes == 0 {
+            problems.push("max_body_bytes must be non-zero, or every request body is rejected");
+        }
+        if self.max_bulk_body_bytes == 0 {
+            problems.push("max_bulk_body_bytes must be non-zero, or every bulk import is rejected");
+        }
+        if self.rate_limit_per_minute == 0 {
+            problems.push("rate_limit_per_minute must be non-zero, or every request is rejected");
+        }
+        for entry in &self.trusted_proxies {
+            if crate::ratelimit::parse_trusted_proxy(entry).is_none() {
+                problems.push("trusted_proxies entries must be an IP or CIDR block, e.g. \"10.0.0.0/8\"");
+                break;
+            }
+        }
+        if self.horizon_url.trim().is_empty() {
+            problems.push("horizon_url must not be empty");
+        }
+        if self.horizon_testnet_url.trim().is_empty() {
+            problems.push("horizon_testnet_url must not be empty");
+        }
+        if self.horizon_request_timeout_secs == 0 {
+            problems.push(
+                "horizon_request_timeout_secs must be non-zero, or every Horizon call times out instantly",
+            );
+        }
+        if self.horizon_rate_limit_per_second == 0 {
+            problems.push(
+                "horizon_rate_limit_per_second must be non-zero, or every Horizon call blocks forever",
+            );
+        }
+        if self.db_max_connections == 0 {
+            problems.push(
+                "db_max_connections must be non-zero, or the pool can never hand out a connection",
+            );
+        }
+        if self.db_acquire_timeout_secs == 0 {
+            problems.push("db_acquire_timeout_secs must be non-zero, or every acquire times out instantly");
+        }
+        if self.default_page_size <= 0 {
+            problems.push("default_page_size must be a positive number");
+        }
+        if self.max_page_size <= 0 {
+            problems.push("max_page_size must be a positive number");
+        }
+        if self.default_page_size > self.max_page_size {
+            problems.push("default_page_size must not exceed max_page_size");
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("invalid configuration:\n  - {}", problems.join("\n  - "));
+        }
+    }
+
+    pub fn server_addr(&self) -> String {
+        format!("{}:{}", self.server_host, self.server_port)
+    }
+
+    /// Parses `trusted_proxies` into the form `RateLimiter::new` wants.
+    /// Panics on an invalid entry, which `validate` already rules out for
+    /// any `Config` that made it through `load`.
+    pub fn parsed_trusted_proxies(&self) -> Vec<crate::ratelimit::TrustedProxy> {
+        self.trusted_proxies
+            .iter()
+            .map(|entry| {
+                crate::ratelimit::parse_trusted_proxy(entry)
+                    .unwrap_or_else(|| panic!("invalid trusted_proxies entry: {entry:?}"))
+            })
+            .collect()
+    }
+}
+
+/// Accepts both a comma-separated string (as read from the environment)
+/// and a native TOML array (as written in `config.toml`). Used for both
+/// `cors_allowed_origins` and `cors_allowed_headers`.
+fn deserialize_csv_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum CsvList {
+        Csv(String),
+        List(Vec<String>),
+    }
+
+    Ok(match CsvList::deserialize(deserializer)? {
+        CsvList::Csv(s) => s
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect(),
+        CsvList::List(list) => list,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct CsvWrapper {
+        #[serde(deserialize_with = "deserialize_csv_list")]
+        values: Vec<String>,
+    }
+
+    #[test]
+    fn csv_list_splits_and_trims() {
+        let parsed: CsvWrapper = serde_json::from_str(r#"{"values": " a, b ,, c "}"#).unwrap();
+        assert_eq!(parsed.values, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn csv_list_accepts_native_list() {
+        let parsed: CsvWrapper = serde_json::from_str(r#"{"values": ["a", "b"]}"#).unwrap();
+        assert_eq!(parsed.values, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn validate_accepts_defaults() {
+        assert!(Config::default().validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_zero_port() {
+        let config = Config {
+            server_port: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_empty_host() {
+        let config = Config {
+            server_host: "  ".to_string(),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_zero_request_timeout() {
+        let config = Config {
+            request_timeout_secs: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_zero_max_body_bytes() {
+        let config = Config {
+            max_body_bytes: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_zero_max_bulk_body_bytes() {
+        let config = Config {
+            max_bulk_body_bytes: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_zero_rate_limit() {
+        let config = Config {
+            rate_limit_per_minute: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_empty_horizon_url() {
+        let config = Config {
+            horizon_url: "  ".to_string(),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_empty_horizon_testnet_url() {
+        let config = Config {
+            horizon_testnet_url: "  ".to_string(),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_zero_horizon_request_timeout() {
+        let config = Config {
+            horizon_request_timeout_secs: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_zero_horizon_rate_limit() {
+        let config = Config {
+            horizon_rate_limit_per_second: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_zero_db_max_connections() {
+        let config = Config {
+            db_max_connections: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_zero_db_acquire_timeout() {
+        let config = Config {
+            db_acquire_timeout_secs: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_zero_default_page_size() {
+        let config = Config {
+            default_page_size: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_zero_max_page_size() {
+        let config = Config {
+            max_page_size: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_a_malformed_trusted_proxy() {
+        let config = Config {
+            trusted_proxies: vec!["not-an-ip".to_string()],
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_accepts_a_trusted_proxy_cidr() {
+        let config = Config {
+            trusted_proxies: vec!["10.0.0.0/8".to_string()],
+            ..Config::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_default_page_size_above_max() {
+        let config = Config {
+            default_page_size: 100,
+            max_page_size: 20,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_reports_every_problem_at_once() {
+        let config = Config {
+            server_port: 0,
+            server_host: "  ".to_string(),
+            rate_limit_per_minute: 0,
+            ..Config::default()
+        };
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("server_port"));
+        assert!(err.contains("server_host"));
+        assert!(err.contains("rate_limit_per_minute"));
+    }
+}
@@ -0,0 +1,45 @@
+//! A drop-in replacement for `axum::Json` that reports deserialization
+//! failures as [`crate::error::AppError::InvalidJson`] instead of axum's
+//! terse built-in rejection, so the frontend gets a field path and the
+//! expected type instead of a single opaque message.
+
+use async_trait::async_trait;
+use axum::extract::{FromRequest, Request};
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+
+use crate::error::{AppError, FieldError};
+
+pub struct Json<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(|err| AppError::Validation(err.to_string()))?;
+
+        from_slice(&bytes).map(Json)
+    }
+}
+
+/// Deserializes `bytes` the same way [`Json`]'s `FromRequest` impl does.
+/// Exists for handlers that must inspect the raw body themselves before
+/// they know which shape to deserialize into (e.g.
+/// `handlers::update_anchor`, which branches on `Content-Type` to support
+/// both a JSON-merge and a JSON-Patch body on the same route) and so
+/// can't go through the extractor directly.
+pub fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, AppError> {
+    let deserializer = &mut serde_json::Deserializer::from_slice(bytes);
+    serde_path_to_error::deserialize(deserializer).map_err(|err| {
+        let field = err.path().to_string();
+        let message = err.into_inner().to_string();
+        AppError::InvalidJson(vec![FieldError { field, message }])
+    })
+}
@@ -0,0 +1,212 @@
+//! `GET /health/detailed` — aggregated status of everything this service
+//! depends on, for monitoring that wants one endpoint to poll instead of
+//! stitching together `/ready` with ad hoc checks of its own. Unlike
+//! [`crate::handlers::health_check`] (never touches anything external) and
+//! [`crate::handlers::readiness_check`] (one boolean for the database
+//! alone), this reports a named entry per dependency so a dashboard can
+//! show which one is actually down. Adding a dependency means adding one
+//! `check_*` function here and one call to it in
+//! [`crate::handlers::detailed_health_check`] — nothing else in this module
+//! needs to change.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::database::Database;
+use crate::horizon::HorizonClient;
+
+/// How long a single dependency check is allowed to take before it's
+/// reported `down` rather than left to hang the whole response.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyStatus {
+    Ok,
+    Degraded,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DependencyHealth {
+    pub name: &'static str,
+    pub status: DependencyStatus,
+    pub latency_ms: u128,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DetailedHealthResponse {
+    pub status: DependencyStatus,
+    pub dependencies: Vec<DependencyHealth>,
+}
+
+/// The overall status for a set of dependency checks: the worst of the
+/// individual statuses, so a single degraded or down dependency is never
+/// masked by the rest reporting `ok`.
+fn aggregate_status(dependencies: &[DependencyHealth]) -> DependencyStatus {
+    if dependencies.iter().any(|dep| dep.status == DependencyStatus::Down) {
+        DependencyStatus::Down
+    } else if dependencies.iter().any(|dep| dep.status == DependencyStatus::Degraded) {
+        DependencyStatus::Degraded
+    } else {
+        DependencyStatus::Ok
+    }
+}
+
+/// Pings the database with the same query [`crate::handlers::readiness_check`]
+/// uses, timed and capped at [`CHECK_TIMEOUT`] rather than left to
+/// [`crate::database::Database::ping`]'s own pool timeout.
+async fn check_database(db: &Database) -> DependencyHealth {
+    let start = Instant::now();
+    let outcome = tokio::time::timeout(CHECK_TIMEOUT, db.ping()).await;
+    let latency_ms = start.elapsed().as_millis();
+
+    match outcome {
+        Ok(Ok(())) => DependencyHealth {
+            name: "database",
+            status: DependencyStatus::Ok,
+            latency_ms,
+            detail: None,
+        },
+        Ok(Err(err)) => DependencyHealth {
+            name: "database",
+            status: DependencyStatus::Down,
+            latency_ms,
+            detail: Some(err.to_string()),
+        },
+        Err(_) => DependencyHealth {
+            name: "database",
+            status: DependencyStatus::Down,
+            latency_ms,
+            detail: Some(format!("timed out after {CHECK_TIMEOUT:?}")),
+        },
+    }
+}
+
+/// Degraded, not down, if migrations were fetched fine but one of them
+/// recorded `success = false` — the service is running against a schema
+/// that a migration run didn't fully apply, which is a warning sign rather
+/// than an outage.
+async fn check_migrations(db: &Database) -> DependencyHealth {
+    let start = Instant::now();
+    let outcome = tokio::time::timeout(CHECK_TIMEOUT, db.list_applied_migrations()).await;
+    let latency_ms = start.elapsed().as_millis();
+
+    match outcome {
+        Ok(Ok(migrations)) => {
+            let failed: Vec<_> = migrations.iter().filter(|m| !m.success).collect();
+            if failed.is_empty() {
+                DependencyHealth {
+                    name: "migrations",
+                    status: DependencyStatus::Ok,
+                    latency_ms,
+                    detail: None,
+                }
+            } else {
+                DependencyHealth {
+                    name: "migrations",
+                    status: DependencyStatus::Degraded,
+                    latency_ms,
+                    detail: Some(format!("{} migration(s) did not apply cleanly", failed.len())),
+                }
+            }
+        }
+        Ok(Err(err)) => DependencyHealth {
+            name: "migrations",
+            status: DependencyStatus::Down,
+            latency_ms,
+            detail: Some(err.to_string()),
+        },
+        Err(_) => DependencyHealth {
+            name: "migrations",
+            status: DependencyStatus::Down,
+            latency_ms,
+            detail: Some(format!("timed out after {CHECK_TIMEOUT:?}")),
+        },
+    }
+}
+
+/// A lightweight Horizon reachability check: hits Horizon's own
+/// [`HorizonClient::ping`], which bypasses the shared outbound rate limiter
+/// so operational polling never competes with real anchor traffic for it.
+async fn check_horizon(horizon: &HorizonClient) -> DependencyHealth {
+    let start = Instant::now();
+    let outcome = tokio::time::timeout(CHECK_TIMEOUT, horizon.ping()).await;
+    let latency_ms = start.elapsed().as_millis();
+
+    match outcome {
+        Ok(Ok(())) => DependencyHealth {
+            name: "horizon",
+            status: DependencyStatus::Ok,
+            latency_ms,
+            detail: None,
+        },
+        Ok(Err(err)) => DependencyHealth {
+            name: "horizon",
+            status: DependencyStatus::Degraded,
+            latency_ms,
+            detail: Some(err.to_string()),
+        },
+        Err(_) => DependencyHealth {
+            name: "horizon",
+            status: DependencyStatus::Degraded,
+            latency_ms,
+            detail: Some(format!("timed out after {CHECK_TIMEOUT:?}")),
+        },
+    }
+}
+
+/// Runs every dependency check and folds them into one response. Horizon
+/// being unreachable is reported as `degraded`, not `down`: this service
+/// keeps serving anchor data from its own database even when Horizon is
+/// unavailable, so the process itself isn't unhealthy, just running with
+/// reduced capability.
+pub async fn collect(db: &Database, horizon: &HorizonClient) -> DetailedHealthResponse {
+    let dependencies = vec![
+        check_database(db).await,
+        check_migrations(db).await,
+        check_horizon(horizon).await,
+    ];
+    let status = aggregate_status(&dependencies);
+    DetailedHealthResponse { status, dependencies }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(status: DependencyStatus) -> DependencyHealth {
+        DependencyHealth {
+            name: "test",
+            status,
+            latency_ms: 0,
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn aggregate_status_is_ok_when_everything_is_ok() {
+        let deps = vec![health(DependencyStatus::Ok), health(DependencyStatus::Ok)];
+        assert_eq!(aggregate_status(&deps), DependencyStatus::Ok);
+    }
+
+    #[test]
+    fn aggregate_status_prefers_down_over_degraded() {
+        let deps = vec![health(DependencyStatus::Degraded), health(DependencyStatus::Down)];
+        assert_eq!(aggregate_status(&deps), DependencyStatus::Down);
+    }
+
+    #[test]
+    fn aggregate_status_is_degraded_if_nothing_is_down() {
+        let deps = vec![health(DependencyStatus::Ok), health(DependencyStatus::Degraded)];
+        assert_eq!(aggregate_status(&deps), DependencyStatus::Degraded);
+    }
+
+    #[test]
+    fn aggregate_status_of_no_dependencies_is_ok() {
+        assert_eq!(aggregate_status(&[]), DependencyStatus::Ok);
+    }
+}
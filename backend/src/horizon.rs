@@ -0,0 +1,206 @@
+//! Shared Horizon client.
+//!
+//! Account lookups (`create_anchor`, `verify_anchor_asset`) and payment
+//! ingestion (`ingest`) each used to build their own `reqwest::Client` and
+//! call Horizon directly, so nothing kept the app's combined outbound rate
+//! under Horizon's own limits — a burst of anchor creations landing during
+//! an ingest pass could trip them. [`HorizonClient`] centralizes every
+//! Horizon call behind one pooled `reqwest::Client` and one internal token
+//! bucket shared across all of them.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tokio::sync::Mutex;
+
+use crate::stellar::{fetch_account, fetch_payment_volume, HorizonAccount, HorizonError, PaymentVolume};
+
+/// Shared client for every outbound call to Horizon. Holds both the mainnet
+/// (`public_url`) and testnet (`testnet_url`) base URLs, configured via
+/// `HORIZON_URL`/`HORIZON_TESTNET_URL` (see [`crate::config::Config`]), and
+/// picks between them per call based on the anchor's own
+/// [`crate::database::ANCHOR_NETWORKS`] value — this service tracks anchors
+/// on both networks at once, so a single fixed base URL isn't enough. Cheap
+/// to clone: every clone reuses the same connection pool and rate limiter,
+/// which is shared across both networks rather than split per-network, to
+/// keep the combined outbound rate meaningful.
+#[derive(Clone)]
+pub struct HorizonClient {
+    client: reqwest::Client,
+    public_url: String,
+    testnet_url: String,
+    limiter: RateLimiter,
+}
+
+impl HorizonClient {
+    /// Builds a client with a `request_timeout` on every call and a
+    /// combined request rate capped at `rate_limit_per_second`.
+    pub fn new(
+        public_url: String,
+        testnet_url: String,
+        request_timeout: Duration,
+        rate_limit_per_second: u32,
+    ) -> reqwest::Result<Self> {
+        let client = reqwest::Client::builder().timeout(request_timeout).build()?;
+        Ok(Self {
+            client,
+            public_url,
+            testnet_url,
+            limiter: RateLimiter::new(rate_limit_per_second),
+        })
+    }
+
+    /// Resolves `network` (an [`ANCHOR_NETWORKS`] value) to the matching
+    /// base URL. Anything other than `"testnet"` is treated as `"public"`,
+    /// matching [`crate::database::DEFAULT_ANCHOR_NETWORK`] — callers are
+    /// expected to have already validated `network` against
+    /// `ANCHOR_NETWORKS` before it gets this far.
+    fn base_url(&self, network: &str) -> &str {
+        if network == "testnet" {
+            &self.testnet_url
+        } else {
+            &self.public_url
+        }
+    }
+
+    /// Fetches `stellar_account`'s balances and home domain from `network`'s
+    /// Horizon instance. Waits for the internal rate limiter first if
+    /// Horizon calls are coming in faster than this client allows. See
+    /// [`crate::stellar::fetch_account`].
+    pub async fn fetch_account(
+        &self,
+        network: &str,
+        stellar_account: &str,
+    ) -> Result<HorizonAccount, HorizonError> {
+        self.limiter.acquire().await;
+        fetch_account(&self.client, self.base_url(network), stellar_account).await
+    }
+
+    /// Sums `account`'s payment volume since `since` on `network`, rate
+    /// limited the same as [`Self::fetch_account`]. See
+    /// [`crate::stellar::fetch_payment_volume`].
+    pub async fn fetch_payment_volume(
+        &self,
+        network: &str,
+        account: &str,
+        since: DateTime<Utc>,
+    ) -> Result<PaymentVolume, HorizonError> {
+        self.limiter.acquire().await;
+        fetch_payment_volume(&self.client, self.base_url(network), account, since).await
+    }
+
+    /// A bare reachability check against the public network's Horizon root,
+    /// for `GET /health/detailed` (see [`crate::health`]). Deliberately
+    /// skips [`RateLimiter::acquire`]: an operator polling health shouldn't
+    /// have to wait behind real anchor traffic for a token, and a health
+    /// check that itself gets rate limited defeats the point.
+    pub async fn ping(&self) -> Result<(), HorizonError> {
+        self.client
+            .get(&self.public_url)
+            .send()
+            .await
+            .map_err(HorizonError::Request)?
+            .error_for_status()
+            .map_err(HorizonError::Request)?;
+        Ok(())
+    }
+}
+
+/// A single token bucket shared by every call this process makes to
+/// Horizon. Unlike [`crate::ratelimit::RateLimiter`] (one bucket per caller
+/// IP, rejects with 429 once dry), this is one bucket for the whole
+/// process, and [`RateLimiter::acquire`] waits for a token to refill rather
+/// than failing: an internal client should slow itself down, not give up,
+/// when it's the one about to trip Horizon's own limit.
+#[derive(Clone)]
+struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+    per_second: f64,
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    fn new(per_second: u32) -> Self {
+        let capacity = per_second as f64;
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            })),
+            per_second: capacity,
+        }
+    }
+
+    /// Waits, if necessary, for one token to refill and consumes it.
+    async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().await;
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+                bucket.tokens = (bucket.tokens + elapsed * self.per_second).min(self.per_second);
+                bucket.last_refill = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    None
+                } else {
+                    Some(Duration::from_secs_f64((1.0 - bucket.tokens) / self.per_second))
+                }
+            };
+
+            match wait {
+                None => return,
+                Some(duration) => tokio::time::sleep(duration).await,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> HorizonClient {
+        HorizonClient::new(
+            "https://horizon.stellar.org".to_string(),
+            "https://horizon-testnet.stellar.org".to_string(),
+            Duration::from_secs(10),
+            10,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn base_url_picks_testnet_only_for_testnet() {
+        let client = client();
+        assert_eq!(client.base_url("testnet"), "https://horizon-testnet.stellar.org");
+        assert_eq!(client.base_url("public"), "https://horizon.stellar.org");
+    }
+
+    #[tokio::test]
+    async fn acquire_does_not_wait_within_capacity() {
+        let limiter = RateLimiter::new(5);
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn acquire_waits_once_the_bucket_is_empty() {
+        let limiter = RateLimiter::new(100);
+        for _ in 0..100 {
+            limiter.acquire().await;
+        }
+        let start = Instant::now();
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(5));
+    }
+}
@@ -0,0 +1,124 @@
+//! Background job that periodically recomputes each anchor's 24h payment
+//! volume from actual Horizon activity, replacing the manually reported
+//! figure a human previously entered by hand.
+//!
+//! Runs as a plain `tokio::spawn` loop on a `tokio::time::interval` rather
+//! than through the `apalis` cron worker in `worker.rs`: unlike that
+//! worker's cron schedule, this only ever needs a fixed polling period, so
+//! a plain interval is simpler and avoids pulling in apalis machinery for a
+//! job with no cron semantics.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio_util::sync::CancellationToken;
+
+use crate::database::Database;
+use crate::horizon::HorizonClient;
+use crate::metrics::AnchorMetrics;
+
+const DEFAULT_INTERVAL_SECS: u64 = 3600;
+
+/// How far back each pass looks for payments. Fixed rather than configured,
+/// since "24h transfer volume" is the metric this job exists to produce —
+/// changing the window would change what `total_volume` means, not just
+/// how often it's refreshed.
+const VOLUME_WINDOW: chrono::Duration = chrono::Duration::hours(24);
+
+/// Whether the job runs at all. Set `INGEST_ENABLED=false` (or `0`) to turn
+/// it off entirely — e.g. in an environment pointed at a Horizon instance
+/// with no real payment history worth polling.
+fn ingest_enabled() -> bool {
+    std::env::var("INGEST_ENABLED")
+        .map(|value| value != "false" && value != "0")
+        .unwrap_or(true)
+}
+
+/// Walks every anchor, sums its Horizon payment volume over the last 24h,
+/// and writes it through the same [`Database::update_anchor_metrics`] path
+/// `PUT /api/anchors/:id/metrics` uses. A single anchor's Horizon failure is
+/// logged and skipped rather than aborting the whole pass.
+///
+/// `pub(crate)` so `worker::refresh_all_anchor_metrics` can drive the same
+/// Horizon-backed computation on its own cron schedule, rather than the
+/// zeroed-out placeholder it used before real Horizon ingestion existed.
+pub(crate) async fn ingest_all_anchor_volumes(db: &Database, horizon: &HorizonClient) {
+    let anchors = match db.list_anchors().await {
+        Ok(anchors) => anchors,
+        Err(err) => {
+            tracing::error!("payment ingest: failed to list anchors: {err}");
+            return;
+        }
+    };
+
+    let since = Utc::now() - VOLUME_WINDOW;
+
+    for anchor in anchors {
+        let volume = match horizon
+            .fetch_payment_volume(&anchor.network, &anchor.stellar_account, since)
+            .await
+        {
+            Ok(volume) => volume,
+            Err(err) => {
+                tracing::warn!(
+                    "payment ingest: failed to fetch payments for {}: {err}",
+                    anchor.stellar_account
+                );
+                continue;
+            }
+        };
+
+        let metrics = AnchorMetrics {
+            transaction_count: volume.transaction_count,
+            total_volume: volume.total_volume,
+            success_rate: volume.success_rate,
+        };
+
+        if let Err(err) = db.update_anchor_metrics(anchor.id, metrics, None, None).await {
+            tracing::error!(
+                "payment ingest: failed to persist volume for {}: {err}",
+                anchor.stellar_account
+            );
+        }
+    }
+
+    tracing::info!("payment ingest: cycle complete");
+}
+
+/// Spawns the ingestion loop as a background task, ticking every
+/// `INGEST_INTERVAL_SECS` seconds (default 3600). A no-op task if
+/// `INGEST_ENABLED` says the job is disabled. `shutdown` is shared with the
+/// rest of `main`'s background tasks: once cancelled, the loop stops after
+/// its in-flight pass (if any) finishes.
+pub fn spawn(
+    db: Arc<Database>,
+    horizon: HorizonClient,
+    shutdown: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        if !ingest_enabled() {
+            tracing::info!("payment ingest: disabled via INGEST_ENABLED, not starting");
+            return;
+        }
+
+        let interval_secs = std::env::var("INGEST_INTERVAL_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_INTERVAL_SECS);
+        tracing::info!("payment ingest scheduled every {interval_secs}s");
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    ingest_all_anchor_volumes(&db, &horizon).await;
+                }
+                _ = shutdown.cancelled() => {
+                    tracing::info!("payment ingest: shutdown signal received, stopping");
+                    break;
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,188 @@
+//! Maintenance-mode write guard.
+//!
+//! During a migration we want reads to keep working but writes to fail
+//! cleanly with a clear "try again shortly" instead of racing the schema
+//! change or half-applying mid-flight. `MaintenanceMode` is a shared,
+//! runtime-toggleable flag: it starts from `MAINTENANCE_MODE` (via
+//! [`crate::config::Config`]) and can also be flipped without a restart
+//! through the `/api/admin/maintenance` routes below, mirroring
+//! [`crate::cors::OriginRegistry`]'s live-registry-plus-management-routes
+//! shape. `main` applies [`reject_during_maintenance`] as a `route_layer`
+//! to the write route groups only; read routes never see it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use subtle::ConstantTimeEq;
+
+use crate::error::AppError;
+
+/// Suggested wait, in seconds, sent back on the `Retry-After` header of a
+/// rejected write. Fixed rather than configurable: an operator running a
+/// migration doesn't need to tune this, and a fixed value keeps clients from
+/// hammering the endpoint immediately after the first rejection.
+const MAINTENANCE_RETRY_AFTER_SECS: u64 = 60;
+
+/// Shared, lock-free maintenance flag. Cheap to clone; every clone toggles
+/// and observes the same underlying flag.
+#[derive(Clone)]
+pub struct MaintenanceMode {
+    active: Arc<AtomicBool>,
+}
+
+impl MaintenanceMode {
+    pub fn new(active: bool) -> Self {
+        Self {
+            active: Arc::new(AtomicBool::new(active)),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self, active: bool) {
+        self.active.store(active, Ordering::Relaxed);
+    }
+}
+
+/// Axum middleware: once `mode` is active, every request through this layer
+/// is rejected with `503 Service Unavailable` and a `Retry-After` header
+/// instead of reaching the handler. Applied only to write route groups in
+/// `main`, so GETs keep working throughout the maintenance window.
+pub async fn reject_during_maintenance(
+    State(mode): State<MaintenanceMode>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !mode.is_active() {
+        return next.run(request).await;
+    }
+
+    let mut response = AppError::MaintenanceMode.into_response();
+    if let Ok(value) = HeaderValue::from_str(&MAINTENANCE_RETRY_AFTER_SECS.to_string()) {
+        response.headers_mut().insert("Retry-After", value);
+    }
+    response
+}
+
+/// `POST /api/admin/maintenance` — turn maintenance mode on.
+#[utoipa::path(
+    post,
+    path = "/api/admin/maintenance",
+    tag = "admin",
+    responses(
+        (status = 204, description = "Maintenance mode is now active; write routes reject with 503"),
+        (status = 401, description = "Missing or wrong ADMIN_API_TOKEN bearer token"),
+    )
+)]
+pub async fn enable_maintenance(
+    State(mode): State<MaintenanceMode>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_authorized(&headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    mode.set(true);
+    StatusCode::NO_CONTENT.into_response()
+}
+
+/// `POST /api/admin/maintenance/clear` — turn maintenance mode back off.
+#[utoipa::path(
+    post,
+    path = "/api/admin/maintenance/clear",
+    tag = "admin",
+    responses(
+        (status = 204, description = "Maintenance mode is now inactive; write routes behave normally"),
+        (status = 401, description = "Missing or wrong ADMIN_API_TOKEN bearer token"),
+    )
+)]
+pub async fn disable_maintenance(
+    State(mode): State<MaintenanceMode>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_authorized(&headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    mode.set(false);
+    StatusCode::NO_CONTENT.into_response()
+}
+
+/// Minimal bearer-token check against `ADMIN_API_TOKEN`, same as
+/// `cors::is_authorized`. Kept as its own copy rather than a shared helper:
+/// each management module owns its authorization check the same way
+/// `auth::is_authorized` owns `API_KEYS`, so none of them depend on another
+/// module's internals just to gate a route.
+fn is_authorized(headers: &HeaderMap) -> bool {
+    let Ok(expected) = std::env::var("ADMIN_API_TOKEN") else {
+        return false;
+    };
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| {
+            token.len() == expected.len() && token.as_bytes().ct_eq(expected.as_bytes()).into()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_mode_reflects_initial_state() {
+        assert!(!MaintenanceMode::new(false).is_active());
+        assert!(MaintenanceMode::new(true).is_active());
+    }
+
+    #[test]
+    fn set_toggles_the_flag() {
+        let mode = MaintenanceMode::new(false);
+        mode.set(true);
+        assert!(mode.is_active());
+        mode.set(false);
+        assert!(!mode.is_active());
+    }
+
+    #[test]
+    fn clones_share_the_same_flag() {
+        let mode = MaintenanceMode::new(false);
+        let clone = mode.clone();
+        clone.set(true);
+        assert!(mode.is_active());
+    }
+
+    // `ADMIN_API_TOKEN` is process-global, so these cases live in one test
+    // instead of several that could race on it under parallel test runs.
+    #[test]
+    fn is_authorized_cases() {
+        std::env::set_var("ADMIN_API_TOKEN", "s3cret-token");
+
+        let headers = HeaderMap::new();
+        assert!(!is_authorized(&headers), "missing header is unauthorized");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer wrong-token"),
+        );
+        assert!(!is_authorized(&headers), "wrong token is unauthorized");
+
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer s3cret-token"),
+        );
+        assert!(is_authorized(&headers), "correct bearer token is authorized");
+
+        std::env::remove_var("ADMIN_API_TOKEN");
+    }
+}
@@ -0,0 +1,276 @@
+//! Anchor metric shapes and validation.
+//!
+//! Metrics themselves are computed from real Stellar Horizon activity by
+//! `crate::ingest::ingest_all_anchor_volumes`, shared by both `ingest::spawn`
+//! and `worker::spawn`'s cron jobs; this module holds the [`AnchorMetrics`]
+//! shape both write through, [`validate_metrics`], and the live-metrics
+//! broadcast machinery.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, RwLock};
+
+/// Number of unread messages a `GET /api/anchors/:id/metrics/live` client
+/// can fall behind by before it starts missing updates. Live metrics are
+/// meant to be watched, not replayed, so a slow client drops old messages
+/// (see [`broadcast::error::RecvError::Lagged`] handling in `handlers`)
+/// rather than backing up the channel.
+const LIVE_METRICS_CHANNEL_CAPACITY: usize = 16;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AnchorMetrics {
+    pub transaction_count: i64,
+    /// Stored and serialized as a [`Decimal`], not `f64`: this is a
+    /// reported on-chain total that has to reconcile exactly, and floats
+    /// accumulate rounding drift that a plain sum over many anchors makes
+    /// visible.
+    #[schema(value_type = String)]
+    pub total_volume: Decimal,
+    /// Fraction of successful transactions, `0.0`–`1.0` (not a 0–100
+    /// percentage) — see [`validate_metrics`].
+    pub success_rate: f64,
+}
+
+/// Rejects metrics that can't have come from a real refresh: a negative
+/// `transaction_count` or `total_volume`, or a `success_rate` outside
+/// `0.0..=1.0`. Called from `update_anchor_metrics` before the value is
+/// written, so garbage never reaches `anchor_metrics_history` and skews
+/// [`crate::database::Database::aggregate_metrics`].
+pub fn validate_metrics(metrics: &AnchorMetrics) -> Result<(), String> {
+    if metrics.transaction_count < 0 {
+        return Err("transaction_count must not be negative".to_string());
+    }
+    if metrics.total_volume < Decimal::ZERO {
+        return Err("total_volume must not be negative".to_string());
+    }
+    if !(0.0..=1.0).contains(&metrics.success_rate) {
+        return Err("success_rate must be between 0.0 and 1.0".to_string());
+    }
+    Ok(())
+}
+
+impl Default for AnchorMetrics {
+    fn default() -> Self {
+        Self {
+            transaction_count: 0,
+            total_volume: Decimal::ZERO,
+            success_rate: 0.0,
+        }
+    }
+}
+
+/// Body shape for `PUT /api/anchors/:id/metrics` in its default merge mode:
+/// every field is optional, and a field left out of the request is left
+/// unchanged on the anchor rather than reset to zero. See
+/// [`AnchorMetricsPatch::apply`] and `handlers::update_anchor_metrics`'s
+/// `?replace=true` escape hatch for callers that want the old
+/// whole-object-overwrite behavior instead.
+#[derive(Debug, Clone, Copy, Default, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AnchorMetricsPatch {
+    pub transaction_count: Option<i64>,
+    #[schema(value_type = Option<String>)]
+    pub total_volume: Option<Decimal>,
+    pub success_rate: Option<f64>,
+}
+
+impl AnchorMetricsPatch {
+    /// Overlays this patch onto `base`, keeping `base`'s value for any
+    /// field the patch left `None`.
+    pub fn apply(self, base: AnchorMetrics) -> AnchorMetrics {
+        AnchorMetrics {
+            transaction_count: self.transaction_count.unwrap_or(base.transaction_count),
+            total_volume: self.total_volume.unwrap_or(base.total_volume),
+            success_rate: self.success_rate.unwrap_or(base.success_rate),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AssetMetrics {
+    #[schema(value_type = String)]
+    pub total_volume: Decimal,
+    pub holder_count: i64,
+}
+
+/// Rejects metrics that can't have come from a real refresh: a negative
+/// `total_volume` or `holder_count`. Mirrors [`validate_metrics`]; called
+/// from `update_asset_metrics` before the value is written.
+pub fn validate_asset_metrics(metrics: &AssetMetrics) -> Result<(), String> {
+    if metrics.total_volume < Decimal::ZERO {
+        return Err("total_volume must not be negative".to_string());
+    }
+    if metrics.holder_count < 0 {
+        return Err("holder_count must not be negative".to_string());
+    }
+    Ok(())
+}
+
+impl Default for AssetMetrics {
+    fn default() -> Self {
+        Self {
+            total_volume: Decimal::ZERO,
+            holder_count: 0,
+        }
+    }
+}
+
+/// Fans out metrics updates to `GET /api/anchors/:id/metrics/live` websocket
+/// clients. One `broadcast` channel per anchor id, created lazily on first
+/// subscribe; `update_anchor_metrics` publishes into it after writing the
+/// new row. A channel with no subscribers is a no-op send, and each
+/// subscriber's connection lives entirely in its own task in `handlers`, so
+/// a disconnected client is dropped as soon as its socket closes rather
+/// than lingering here.
+#[derive(Clone, Default)]
+pub struct MetricsBroadcaster {
+    channels: Arc<RwLock<HashMap<i64, broadcast::Sender<AnchorMetrics>>>>,
+}
+
+impl MetricsBroadcaster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn subscribe(&self, anchor_id: i64) -> broadcast::Receiver<AnchorMetrics> {
+        if let Some(sender) = self.channels.read().await.get(&anchor_id) {
+            return sender.subscribe();
+        }
+
+        let mut channels = self.channels.write().await;
+        channels
+            .entry(anchor_id)
+            .or_insert_with(|| broadcast::channel(LIVE_METRICS_CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    pub async fn publish(&self, anchor_id: i64, metrics: AnchorMetrics) {
+        if let Some(sender) = self.channels.read().await.get(&anchor_id) {
+            // No receivers is an expected, non-error case (nobody's watching).
+            let _ = sender.send(metrics);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_metrics_accepts_zeroed_defaults() {
+        assert!(validate_metrics(&AnchorMetrics::default()).is_ok());
+    }
+
+    #[test]
+    fn validate_metrics_rejects_negative_transaction_count() {
+        let metrics = AnchorMetrics {
+            transaction_count: -1,
+            ..AnchorMetrics::default()
+        };
+        assert!(validate_metrics(&metrics).is_err());
+    }
+
+    #[test]
+    fn validate_metrics_rejects_negative_total_volume() {
+        let metrics = AnchorMetrics {
+            total_volume: Decimal::new(-1, 2),
+            ..AnchorMetrics::default()
+        };
+        assert!(validate_metrics(&metrics).is_err());
+    }
+
+    #[test]
+    fn validate_metrics_rejects_success_rate_outside_unit_range() {
+        let too_high = AnchorMetrics {
+            success_rate: 1.01,
+            ..AnchorMetrics::default()
+        };
+        assert!(validate_metrics(&too_high).is_err());
+
+        let negative = AnchorMetrics {
+            success_rate: -0.01,
+            ..AnchorMetrics::default()
+        };
+        assert!(validate_metrics(&negative).is_err());
+    }
+
+    #[test]
+    fn validate_asset_metrics_accepts_zeroed_defaults() {
+        assert!(validate_asset_metrics(&AssetMetrics::default()).is_ok());
+    }
+
+    #[test]
+    fn validate_asset_metrics_rejects_negative_total_volume() {
+        let metrics = AssetMetrics {
+            total_volume: Decimal::new(-1, 2),
+            ..AssetMetrics::default()
+        };
+        assert!(validate_asset_metrics(&metrics).is_err());
+    }
+
+    #[test]
+    fn validate_asset_metrics_rejects_negative_holder_count() {
+        let metrics = AssetMetrics {
+            holder_count: -1,
+            ..AssetMetrics::default()
+        };
+        assert!(validate_asset_metrics(&metrics).is_err());
+    }
+
+    #[test]
+    fn validate_metrics_accepts_the_full_unit_range() {
+        let zero = AnchorMetrics {
+            success_rate: 0.0,
+            ..AnchorMetrics::default()
+        };
+        assert!(validate_metrics(&zero).is_ok());
+
+        let one = AnchorMetrics {
+            success_rate: 1.0,
+            ..AnchorMetrics::default()
+        };
+        assert!(validate_metrics(&one).is_ok());
+    }
+
+    #[test]
+    fn anchor_metrics_patch_leaves_unset_fields_unchanged() {
+        let base = AnchorMetrics {
+            transaction_count: 10,
+            total_volume: Decimal::from(100),
+            success_rate: 0.5,
+        };
+        let patch = AnchorMetricsPatch {
+            total_volume: Some(Decimal::from(200)),
+            ..AnchorMetricsPatch::default()
+        };
+
+        let updated = patch.apply(base);
+        assert_eq!(updated.transaction_count, base.transaction_count);
+        assert_eq!(updated.total_volume, Decimal::from(200));
+        assert_eq!(updated.success_rate, base.success_rate);
+    }
+
+    #[test]
+    fn anchor_metrics_patch_with_every_field_fully_overwrites() {
+        let base = AnchorMetrics {
+            transaction_count: 10,
+            total_volume: Decimal::from(100),
+            success_rate: 0.5,
+        };
+        let patch = AnchorMetricsPatch {
+            transaction_count: Some(20),
+            total_volume: Some(Decimal::ZERO),
+            success_rate: Some(1.0),
+        };
+
+        let updated = patch.apply(base);
+        assert_eq!(updated.transaction_count, 20);
+        assert_eq!(updated.total_volume, Decimal::ZERO);
+        assert_eq!(updated.success_rate, 1.0);
+    }
+}
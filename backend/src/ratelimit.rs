@@ -0,0 +1,263 @@
+//! Per-IP request rate limiting.
+//!
+//! A hackathon demo got hammered by a single misbehaving client calling
+//! `list_anchors` thousands of times a second. This is a small in-memory
+//! token bucket keyed on the client's IP (preferring `X-Forwarded-For` when
+//! the immediate peer is a [`Config::trusted_proxies`] entry, since the
+//! service is usually deployed behind a proxy, and falling back to the
+//! socket's peer address otherwise). It's deliberately not backed by Redis
+//! or another shared store — a single instance is all this service runs
+//! today, and adding a distributed limiter can wait until that changes.
+//!
+//! [`Config::trusted_proxies`]: crate::config::Config::trusted_proxies
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use moka::sync::Cache;
+
+/// How long a caller's bucket may sit untouched before it's evicted. Bounds
+/// [`RateLimiter`]'s memory use against a client that cycles through many
+/// distinct IPs (spoofed or otherwise): each one gets a bucket, but idle
+/// ones are reclaimed instead of accumulating forever.
+const BUCKET_IDLE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// One caller's token bucket. `tokens` refills continuously at
+/// `per_minute / 60` tokens per second, capped at `per_minute`, so a caller
+/// can burst up to the full per-minute allowance and then settles into a
+/// steady rate.
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// One `ip` or `ip/prefix-len` entry from [`Config::trusted_proxies`]. A
+/// bare IP is treated as a `/32` (or `/128` for IPv6) — an exact match.
+///
+/// [`Config::trusted_proxies`]: crate::config::Config::trusted_proxies
+#[derive(Debug, Clone, Copy)]
+pub struct TrustedProxy {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl TrustedProxy {
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let shift = 32 - self.prefix_len.min(32);
+                let mask = u32::MAX.checked_shl(shift.into()).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let shift = 128 - self.prefix_len.min(128);
+                let mask = u128::MAX.checked_shl(shift.into()).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Parses one `TRUSTED_PROXIES` entry, e.g. `"10.0.0.0/8"` or a bare
+/// `"127.0.0.1"`. Returns `None` for anything that isn't a valid IP,
+/// optionally followed by `/` and a prefix length that fits the address
+/// family — [`crate::config::Config::validate`] rejects the whole config at
+/// startup if any entry fails to parse, rather than silently ignoring it at
+/// request time.
+pub fn parse_trusted_proxy(entry: &str) -> Option<TrustedProxy> {
+    let (addr, prefix) = entry.split_once('/').unwrap_or((entry, ""));
+    let network: IpAddr = addr.trim().parse().ok()?;
+    let max_prefix = if network.is_ipv4() { 32 } else { 128 };
+    let prefix_len = if prefix.is_empty() {
+        max_prefix
+    } else {
+        prefix.trim().parse().ok()?
+    };
+    (prefix_len <= max_prefix).then_some(TrustedProxy { network, prefix_len })
+}
+
+/// Shared, lock-guarded rate limiter. Cheap to clone; every clone shares
+/// the same bucket cache.
+#[derive(Clone)]
+pub struct RateLimiter {
+    buckets: Cache<IpAddr, Arc<Mutex<Bucket>>>,
+    per_minute: u32,
+    trusted_proxies: Arc<[TrustedProxy]>,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32, trusted_proxies: Vec<TrustedProxy>) -> Self {
+        Self {
+            buckets: Cache::builder().time_to_idle(BUCKET_IDLE_TTL).build(),
+            per_minute,
+            trusted_proxies: trusted_proxies.into(),
+        }
+    }
+
+    /// Consumes one token for `ip`, or returns how long the caller should
+    /// wait before retrying.
+    async fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        let capacity = self.per_minute as f64;
+        let refill_per_sec = capacity / 60.0;
+        let now = Instant::now();
+
+        let bucket = self.buckets.get_with(ip, || {
+            Arc::new(Mutex::new(Bucket {
+                tokens: capacity,
+                last_refill: now,
+            }))
+        });
+        let mut bucket = bucket.lock().unwrap();
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let deficit = 1.0 - bucket.tokens;
+            Err(Duration::from_secs_f64(deficit / refill_per_sec))
+        }
+    }
+}
+
+/// Best-effort client IP: the first hop of `X-Forwarded-For`, but only when
+/// `addr` (the immediate TCP peer) matches one of `trusted_proxies` —
+/// otherwise any caller could defeat the limiter entirely by sending a
+/// fresh spoofed `X-Forwarded-For` on every request, and grow
+/// [`RateLimiter`]'s bucket cache without bound in the process. Falls back
+/// to the socket's peer address whenever the header is absent, malformed,
+/// or the peer isn't a trusted proxy.
+fn client_ip(headers: &HeaderMap, addr: SocketAddr, trusted_proxies: &[TrustedProxy]) -> IpAddr {
+    if !trusted_proxies.iter().any(|proxy| proxy.contains(addr.ip())) {
+        return addr.ip();
+    }
+
+    headers
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .and_then(|first| first.trim().parse::<IpAddr>().ok())
+        .unwrap_or_else(|| addr.ip())
+}
+
+/// Axum middleware: rejects a request with `429 Too Many Requests` and a
+/// `Retry-After` header once the caller's bucket runs dry.
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Response {
+    let ip = client_ip(&headers, addr, &limiter.trusted_proxies);
+
+    match limiter.check(ip).await {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
+            if let Ok(value) = HeaderValue::from_str(&retry_after.as_secs().max(1).to_string()) {
+                response.headers_mut().insert("Retry-After", value);
+            }
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn allows_requests_within_the_limit() {
+        let limiter = RateLimiter::new(60, Vec::new());
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+        for _ in 0..60 {
+            assert!(limiter.check(ip).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_once_the_bucket_is_empty() {
+        let limiter = RateLimiter::new(1, Vec::new());
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+        assert!(limiter.check(ip).await.is_ok());
+        assert!(limiter.check(ip).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn tracks_separate_ips_independently() {
+        let limiter = RateLimiter::new(1, Vec::new());
+        let a: IpAddr = "127.0.0.1".parse().unwrap();
+        let b: IpAddr = "127.0.0.2".parse().unwrap();
+        assert!(limiter.check(a).await.is_ok());
+        assert!(limiter.check(b).await.is_ok());
+    }
+
+    #[test]
+    fn client_ip_ignores_forwarded_header_from_an_untrusted_peer() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("203.0.113.5, 10.0.0.1"),
+        );
+        let addr: SocketAddr = "127.0.0.1:1234".parse().unwrap();
+        assert_eq!(
+            client_ip(&headers, addr, &[]),
+            addr.ip(),
+            "a spoofed header from an unconfigured peer must not override the socket address"
+        );
+    }
+
+    #[test]
+    fn client_ip_prefers_forwarded_header_from_a_trusted_proxy() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("203.0.113.5, 10.0.0.1"),
+        );
+        let addr: SocketAddr = "127.0.0.1:1234".parse().unwrap();
+        let trusted = [parse_trusted_proxy("127.0.0.1").unwrap()];
+        assert_eq!(
+            client_ip(&headers, addr, &trusted),
+            "203.0.113.5".parse::<IpAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn client_ip_falls_back_to_socket_addr() {
+        let headers = HeaderMap::new();
+        let addr: SocketAddr = "127.0.0.1:1234".parse().unwrap();
+        assert_eq!(client_ip(&headers, addr, &[]), addr.ip());
+    }
+
+    #[test]
+    fn parse_trusted_proxy_accepts_a_bare_ip_as_an_exact_match() {
+        let proxy = parse_trusted_proxy("10.0.0.5").unwrap();
+        assert!(proxy.contains("10.0.0.5".parse().unwrap()));
+        assert!(!proxy.contains("10.0.0.6".parse().unwrap()));
+    }
+
+    #[test]
+    fn parse_trusted_proxy_accepts_a_cidr_block() {
+        let proxy = parse_trusted_proxy("10.0.0.0/8").unwrap();
+        assert!(proxy.contains("10.1.2.3".parse().unwrap()));
+        assert!(!proxy.contains("11.0.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn parse_trusted_proxy_rejects_garbage() {
+        assert!(parse_trusted_proxy("not-an-ip").is_none());
+        assert!(parse_trusted_proxy("10.0.0.0/99").is_none());
+    }
+}
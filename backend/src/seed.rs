@@ -0,0 +1,97 @@
+//! Curated demo data for `backend --seed`.
+//!
+//! Standing up a fresh environment for a demo or a new contributor's
+//! machine used to mean POSTing anchors by hand. `seed_demo_data` inserts a
+//! small, fixed set of anchors and their assets instead, and is safe to run
+//! against a database that already has some or all of them: each anchor is
+//! looked up by `stellar_account` first, and only created if it isn't
+//! there yet, so re-running `--seed` never duplicates rows.
+
+use crate::database::{Database, NewAnchor, NewAnchorAsset, DEFAULT_ANCHOR_NETWORK};
+
+struct SeedAsset {
+    asset_code: &'static str,
+    asset_issuer: &'static str,
+}
+
+struct SeedAnchor {
+    stellar_account: &'static str,
+    home_domain: &'static str,
+    name: &'static str,
+    assets: &'static [SeedAsset],
+}
+
+const SEED_ANCHORS: &[SeedAnchor] = &[
+    SeedAnchor {
+        stellar_account: "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H",
+        home_domain: "demo-anchor-one.example.com",
+        name: "Demo Anchor One",
+        assets: &[SeedAsset {
+            asset_code: "USD",
+            asset_issuer: "GABAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEJXA",
+        }],
+    },
+    SeedAnchor {
+        stellar_account: "GABQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQHGPC",
+        home_domain: "demo-anchor-two.example.com",
+        name: "Demo Anchor Two",
+        assets: &[
+            SeedAsset {
+                asset_code: "EUR",
+                asset_issuer: "GACAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAJJHP",
+            },
+            SeedAsset {
+                asset_code: "GBP",
+                asset_issuer: "GACAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAJJHP",
+            },
+        ],
+    },
+    SeedAnchor {
+        stellar_account: "GACQKBIFAUCQKBIFAUCQKBIFAUCQKBIFAUCQKBIFAUCQKBIFAUCQKG7N",
+        home_domain: "demo-anchor-three.example.com",
+        name: "Demo Anchor Three",
+        assets: &[],
+    },
+];
+
+/// Inserts [`SEED_ANCHORS`] and their assets, skipping any anchor whose
+/// `stellar_account` already exists. Returns the number of anchors
+/// actually created, for the caller to report.
+pub async fn seed_demo_data(db: &Database) -> anyhow::Result<usize> {
+    let mut created = 0;
+
+    for seed in SEED_ANCHORS {
+        if db.get_anchor_by_account(seed.stellar_account).await?.is_some() {
+            tracing::info!("skipping existing seed anchor {}", seed.stellar_account);
+            continue;
+        }
+
+        let anchor = db
+            .create_anchor(
+                NewAnchor {
+                    stellar_account: seed.stellar_account.to_string(),
+                    home_domain: seed.home_domain.to_string(),
+                    name: seed.name.to_string(),
+                    network: DEFAULT_ANCHOR_NETWORK.to_string(),
+                },
+                None,
+            )
+            .await?;
+
+        for asset in seed.assets {
+            db.create_anchor_asset(
+                anchor.id,
+                NewAnchorAsset {
+                    asset_code: asset.asset_code.to_string(),
+                    asset_issuer: asset.asset_issuer.to_string(),
+                },
+            )
+            .await?;
+        }
+
+        tracing::info!("seeded anchor {} ({})", seed.name, seed.stellar_account);
+        created += 1;
+    }
+
+    Ok(created)
+}
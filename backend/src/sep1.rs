@@ -0,0 +1,126 @@
+//! SEP-1 `stellar.toml` fetching and parsing.
+//!
+//! Anchors publish an info file at `https://{domain}/.well-known/stellar.toml`
+//! (see [SEP-1](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0001.md))
+//! listing, among other things, every currency they issue under a
+//! `[[CURRENCIES]]` table array. `fetch_toml` pulls just that list, so an
+//! anchor's assets can be kept in sync with what it actually publishes
+//! instead of entered by hand.
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+struct StellarToml {
+    #[serde(default, rename = "CURRENCIES")]
+    currencies: Vec<Currency>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Currency {
+    code: String,
+    issuer: String,
+}
+
+/// One `[[CURRENCIES]]` entry, trimmed to the fields [`Database::sync_anchor_assets_from_toml`](crate::database::Database::sync_anchor_assets_from_toml) needs.
+#[derive(Debug, Clone)]
+pub struct CurrencyEntry {
+    pub code: String,
+    pub issuer: String,
+}
+
+/// A [`fetch_toml`] failure.
+#[derive(Debug)]
+pub enum Sep1Error {
+    /// The domain has no `stellar.toml` published (Horizon-style 404).
+    NotFound,
+    /// The request itself failed — network error, timeout, or a non-404
+    /// error status.
+    Request(reqwest::Error),
+    /// The file was fetched but isn't valid TOML, or doesn't match the
+    /// shape this service expects.
+    Parse(toml::de::Error),
+}
+
+impl std::fmt::Display for Sep1Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Sep1Error::NotFound => write!(f, "no stellar.toml published at this domain"),
+            Sep1Error::Request(err) => write!(f, "stellar.toml request failed: {err}"),
+            Sep1Error::Parse(err) => write!(f, "malformed stellar.toml: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Sep1Error {}
+
+/// Fetches and parses `home_domain`'s `stellar.toml`, returning its
+/// declared currencies. `client` is a caller-owned [`reqwest::Client`] so
+/// repeated calls reuse its connection pool.
+pub async fn fetch_toml(
+    client: &reqwest::Client,
+    home_domain: &str,
+) -> Result<Vec<CurrencyEntry>, Sep1Error> {
+    let url = format!(
+        "https://{}/.well-known/stellar.toml",
+        home_domain.trim_end_matches('/')
+    );
+
+    let response = client.get(url).send().await.map_err(Sep1Error::Request)?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(Sep1Error::NotFound);
+    }
+
+    let body = response
+        .error_for_status()
+        .map_err(Sep1Error::Request)?
+        .text()
+        .await
+        .map_err(Sep1Error::Request)?;
+
+    let parsed: StellarToml = toml::from_str(&body).map_err(Sep1Error::Parse)?;
+    Ok(parsed
+        .currencies
+        .into_iter()
+        .map(|currency| CurrencyEntry {
+            code: currency.code,
+            issuer: currency.issuer,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_currencies_from_a_well_formed_toml_document() {
+        let toml = r#"
+            VERSION = "2.0.0"
+
+            [[CURRENCIES]]
+            code = "USD"
+            issuer = "GISSUER1"
+
+            [[CURRENCIES]]
+            code = "EUR"
+            issuer = "GISSUER2"
+        "#;
+        let parsed: StellarToml = toml::from_str(toml).unwrap();
+        assert_eq!(parsed.currencies.len(), 2);
+        assert_eq!(parsed.currencies[0].code, "USD");
+    }
+
+    #[test]
+    fn missing_currencies_table_defaults_to_empty() {
+        let parsed: StellarToml = toml::from_str(r#"VERSION = "2.0.0""#).unwrap();
+        assert!(parsed.currencies.is_empty());
+    }
+
+    #[test]
+    fn malformed_toml_is_a_parse_error() {
+        let err = toml::from_str::<StellarToml>("not valid = = toml").unwrap_err();
+        let sep1_err = Sep1Error::Parse(err);
+        assert!(sep1_err.to_string().contains("malformed stellar.toml"));
+    }
+}
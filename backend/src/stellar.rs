@@ -0,0 +1,367 @@
+//! Stellar strkey validation and Horizon account lookups.
+//!
+//! A Stellar public key ("G..." address) is a 56-character, unpadded
+//! base32 string that decodes to a version byte, a 32-byte raw ed25519
+//! key and a 2-byte CRC16/XMODEM checksum. Nothing about the HTTP layer
+//! checks this shape today, so a truncated paste silently inserts a
+//! well-formed-looking row that nothing can ever look up again.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const ED25519_PUBLIC_KEY_VERSION: u8 = 6 << 3;
+
+#[derive(Debug, Clone)]
+pub struct ValidationError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+fn invalid(message: impl Into<String>) -> ValidationError {
+    ValidationError {
+        field: "stellar_account",
+        message: message.into(),
+    }
+}
+
+/// Validates that `account` is a well-formed Stellar ed25519 public key:
+/// 56 characters, starting with `G`, decoding as unpadded base32 into a
+/// version byte + 32-byte key + 2-byte CRC16/XMODEM checksum that matches.
+pub fn validate_stellar_account(account: &str) -> Result<(), ValidationError> {
+    decode_ed25519_public_key(account).map(|_| ())
+}
+
+/// Same validation as [`validate_stellar_account`], but returns the raw
+/// 32-byte ed25519 public key instead of discarding it — for
+/// [`crate::ownership`], which needs the key to verify a signature over a
+/// challenge.
+pub fn decode_ed25519_public_key(account: &str) -> Result<[u8; 32], ValidationError> {
+    if account.len() != 56 {
+        return Err(invalid(format!(
+            "must be 56 characters, got {}",
+            account.len()
+        )));
+    }
+    if !account.starts_with('G') {
+        return Err(invalid("must start with 'G'"));
+    }
+
+    let decoded = base32_decode(account).ok_or_else(|| invalid("not valid base32"))?;
+    let (payload, checksum) = decoded
+        .split_last_chunk::<2>()
+        .ok_or_else(|| invalid("decoded key is too short"))?;
+
+    let expected_checksum = crc16_xmodem(payload);
+    let actual_checksum = u16::from_le_bytes(*checksum);
+    if expected_checksum != actual_checksum {
+        return Err(invalid("checksum does not match"));
+    }
+
+    match payload.split_first() {
+        Some((&ED25519_PUBLIC_KEY_VERSION, key)) if key.len() == 32 => {
+            Ok(key.try_into().expect("length checked above"))
+        }
+        _ => Err(invalid("not an ed25519 public key")),
+    }
+}
+
+/// The subset of Horizon's `GET /accounts/{id}` response this service
+/// cares about: the account's declared home domain and its balances.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HorizonAccount {
+    pub home_domain: Option<String>,
+    pub balances: Vec<HorizonBalance>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HorizonBalance {
+    pub asset_type: String,
+    pub asset_code: Option<String>,
+    pub asset_issuer: Option<String>,
+    pub balance: String,
+}
+
+/// A [`fetch_account`] failure.
+#[derive(Debug)]
+pub enum HorizonError {
+    /// Horizon returned 404: no account exists on the network for this key.
+    NotFound,
+    /// The request itself failed — network error, timeout, or a non-404
+    /// error status from Horizon.
+    Request(reqwest::Error),
+}
+
+impl std::fmt::Display for HorizonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HorizonError::NotFound => write!(f, "account not found on network"),
+            HorizonError::Request(err) => write!(f, "horizon request failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for HorizonError {}
+
+/// Fetches `stellar_account`'s current balances and home domain from
+/// Horizon. `base_url` is the Horizon server to query (e.g.
+/// `https://horizon.stellar.org`), and `client` is a caller-owned
+/// [`reqwest::Client`] so repeated calls reuse its connection pool rather
+/// than reconnecting on every anchor creation.
+pub async fn fetch_account(
+    client: &reqwest::Client,
+    base_url: &str,
+    stellar_account: &str,
+) -> Result<HorizonAccount, HorizonError> {
+    let url = format!(
+        "{}/accounts/{}",
+        base_url.trim_end_matches('/'),
+        stellar_account
+    );
+
+    let response = client.get(url).send().await.map_err(HorizonError::Request)?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(HorizonError::NotFound);
+    }
+
+    response
+        .error_for_status()
+        .map_err(HorizonError::Request)?
+        .json::<HorizonAccount>()
+        .await
+        .map_err(HorizonError::Request)
+}
+
+/// Aggregated payment activity for one Stellar account over some window,
+/// as computed by [`fetch_payment_volume`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaymentVolume {
+    pub transaction_count: i64,
+    pub total_volume: Decimal,
+    pub success_rate: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HorizonPaymentsPage {
+    #[serde(rename = "_embedded")]
+    embedded: HorizonPaymentsEmbedded,
+    #[serde(rename = "_links")]
+    links: HorizonPaymentsLinks,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HorizonPaymentsEmbedded {
+    records: Vec<HorizonPaymentRecord>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HorizonPaymentsLinks {
+    next: HorizonLink,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HorizonLink {
+    href: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HorizonPaymentRecord {
+    #[serde(rename = "type")]
+    kind: String,
+    created_at: DateTime<Utc>,
+    transaction_successful: bool,
+    amount: Option<String>,
+}
+
+/// Records fetched per Horizon `/payments` page. Horizon caps this at 200,
+/// which is also the biggest page worth asking for: fewer pages to walk
+/// before hitting `since`.
+const PAYMENTS_PAGE_LIMIT: u32 = 200;
+
+/// Sums `account`'s payment volume since `since` by walking Horizon's
+/// `GET /accounts/{id}/payments` history newest-first (`order=desc`),
+/// stopping as soon as a page's records predate `since` rather than always
+/// paging back to account creation. `include_failed=true` is passed so
+/// `success_rate` reflects failed transactions too, not just the successful
+/// ones Horizon returns by default; only successful amounts count toward
+/// `total_volume`, since a failed payment never actually moved funds. Only
+/// `type: "payment"` records count — `create_account`,
+/// `path_payment_strict_send`, and friends aren't a same-asset transfer of
+/// the kind `AnchorMetrics::total_volume` is meant to track.
+pub async fn fetch_payment_volume(
+    client: &reqwest::Client,
+    base_url: &str,
+    account: &str,
+    since: DateTime<Utc>,
+) -> Result<PaymentVolume, HorizonError> {
+    let mut url = format!(
+        "{}/accounts/{}/payments?order=desc&limit={}&include_failed=true",
+        base_url.trim_end_matches('/'),
+        account,
+        PAYMENTS_PAGE_LIMIT
+    );
+
+    let mut transaction_count = 0i64;
+    let mut successful_count = 0i64;
+    let mut total_volume = Decimal::ZERO;
+
+    loop {
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(HorizonError::Request)?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(HorizonError::NotFound);
+        }
+
+        let page = response
+            .error_for_status()
+            .map_err(HorizonError::Request)?
+            .json::<HorizonPaymentsPage>()
+            .await
+            .map_err(HorizonError::Request)?;
+
+        if page.embedded.records.is_empty() {
+            break;
+        }
+
+        let mut reached_cutoff = false;
+        for record in &page.embedded.records {
+            if record.created_at < since {
+                reached_cutoff = true;
+                break;
+            }
+            if record.kind != "payment" {
+                continue;
+            }
+
+            transaction_count += 1;
+            if record.transaction_successful {
+                successful_count += 1;
+                if let Some(amount) = &record.amount {
+                    total_volume += amount.parse::<Decimal>().unwrap_or(Decimal::ZERO);
+                }
+            }
+        }
+
+        if reached_cutoff {
+            break;
+        }
+        url = page.links.next.href;
+    }
+
+    let success_rate = if transaction_count > 0 {
+        successful_count as f64 / transaction_count as f64
+    } else {
+        1.0
+    };
+
+    Ok(PaymentVolume {
+        transaction_count,
+        total_volume,
+        success_rate,
+    })
+}
+
+/// Decodes an unpadded RFC 4648 base32 string, rejecting characters
+/// outside the standard alphabet.
+fn base32_decode(input: &str) -> Option<Vec<u8>> {
+    let mut bits: u32 = 0;
+    let mut bit_count = 0u32;
+    let mut output = Vec::with_capacity(input.len() * 5 / 8);
+
+    for byte in input.bytes() {
+        let value = BASE32_ALPHABET.iter().position(|&b| b == byte)? as u32;
+        bits = (bits << 5) | value;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            output.push((bits >> bit_count) as u8);
+        }
+    }
+
+    Some(output)
+}
+
+/// CRC16/XMODEM (poly 0x1021, init 0x0000) — the checksum algorithm
+/// Stellar strkeys use.
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0x0000;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A checksum-valid ed25519 public key strkey.
+    const VALID: &str = "GAAACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB7JZX";
+
+    #[test]
+    fn accepts_a_valid_public_key() {
+        assert!(validate_stellar_account(VALID).is_ok());
+    }
+
+    #[test]
+    fn rejects_wrong_length() {
+        let err = validate_stellar_account("GABC").unwrap_err();
+        assert_eq!(err.field, "stellar_account");
+    }
+
+    #[test]
+    fn rejects_missing_g_prefix() {
+        let mut chars: Vec<char> = VALID.chars().collect();
+        chars[0] = 'A';
+        let tampered: String = chars.into_iter().collect();
+        assert!(validate_stellar_account(&tampered).is_err());
+    }
+
+    #[test]
+    fn rejects_bad_checksum() {
+        let mut chars: Vec<char> = VALID.chars().collect();
+        let last = chars.len() - 1;
+        chars[last] = if chars[last] == 'A' { 'B' } else { 'A' };
+        let tampered: String = chars.into_iter().collect();
+        assert!(validate_stellar_account(&tampered).is_err());
+    }
+
+    #[test]
+    fn rejects_non_base32_characters() {
+        let mut tampered = VALID.to_string();
+        tampered.replace_range(1..2, "1");
+        assert!(validate_stellar_account(&tampered).is_err());
+    }
+
+    #[test]
+    fn decode_ed25519_public_key_returns_32_bytes_for_a_valid_account() {
+        let key = decode_ed25519_public_key(VALID).unwrap();
+        assert_eq!(key.len(), 32);
+    }
+
+    #[test]
+    fn decode_ed25519_public_key_rejects_the_same_inputs_validate_stellar_account_rejects() {
+        assert!(decode_ed25519_public_key("GABC").is_err());
+    }
+}
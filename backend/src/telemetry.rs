@@ -0,0 +1,89 @@
+//! Per-request logging and metrics: `main.rs` wires [`make_span`] into a
+//! `TraceLayer` so every request is logged at info level (warn/error for
+//! 5xx) with its method, matched route, status, duration, and the
+//! `X-Request-Id` set by `tower_http::request_id::SetRequestIdLayer`. It
+//! also installs a Prometheus recorder via [`install_recorder`] and layers
+//! [`track_metrics`] to record a counter and histogram of those same
+//! requests, scraped from `GET /metrics` (see `handlers::metrics_endpoint`).
+
+use std::time::{Duration, Instant};
+
+use axum::extract::{MatchedPath, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use tower_http::request_id::RequestId;
+use tracing::Span;
+
+/// Builds the span each request is logged under: method, matched route
+/// (falling back to the raw path for unmatched routes, e.g. 404s), and the
+/// request id set by `SetRequestIdLayer`, which must run before the
+/// `TraceLayer` this is passed to so the id is already on the request's
+/// extensions by the time this runs. `TraceLayer`'s own `on_response`/
+/// `on_failure` log within this span, so every log line carries these
+/// fields without repeating them at each call site.
+pub fn make_span(request: &Request) -> Span {
+    let matched_path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(MatchedPath::as_str);
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .and_then(|id| id.header_value().to_str().ok())
+        .unwrap_or("-");
+
+    tracing::info_span!(
+        "http_request",
+        method = %request.method(),
+        path = matched_path.unwrap_or_else(|| request.uri().path()),
+        request_id = %request_id,
+    )
+}
+
+/// Builds and installs the process-global Prometheus recorder, returning
+/// the handle `handlers::metrics_endpoint` renders on scrape. Doesn't start
+/// its own HTTP listener — this service exposes `/metrics` through the
+/// normal axum router instead, so it shares the app's port, auth, and CORS
+/// posture rather than opening a second one.
+pub fn install_recorder() -> PrometheusHandle {
+    PrometheusBuilder::new()
+        .install_recorder()
+        .expect("failed to install Prometheus metrics recorder")
+}
+
+/// Prometheus's histograms and idle-metric eviction need periodic upkeep
+/// that nothing else in this service would otherwise trigger; runs for the
+/// life of the process, same as the metrics refresh worker.
+pub fn spawn_upkeep(handle: PrometheusHandle) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            handle.run_upkeep();
+        }
+    });
+}
+
+/// Middleware recording `http_requests_total` and
+/// `http_request_duration_seconds`, labeled by method, matched route, and
+/// status, for every request. Layered the same way as [`make_span`]'s
+/// `TraceLayer`, so it sees the same `MatchedPath` extension.
+pub async fn track_metrics(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|matched| matched.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    let latency = start.elapsed().as_secs_f64();
+    let status = response.status().as_u16().to_string();
+
+    let labels = [("method", method), ("path", path), ("status", status)];
+    metrics::counter!("http_requests_total", &labels).increment(1);
+    metrics::histogram!("http_request_duration_seconds", &labels).record(latency);
+
+    response
+}